@@ -0,0 +1,71 @@
+//! Simulated annealing: local search over [`State`] for neighbor graphs too
+//! large to enumerate with the exhaustive minimax in [`crate::Solver`].
+
+use crate::rng::Rng;
+use crate::{Evaluator, Mode, Solver, State};
+
+/// Annealing schedule: starting temperature, geometric cooling factor, and
+/// an iteration budget.
+pub struct Schedule {
+    pub initial_temperature: f64,
+    pub cooling_factor: f64,
+    pub iterations: usize,
+}
+
+impl<E: Evaluator> Solver<E>
+where
+    E::Value: Into<f64>,
+{
+    /// Local search from `root`: repeatedly step to a random neighbor,
+    /// accepting it outright if it improves on the current state (per
+    /// [`Evaluator::mode`]) or, if it's worse, with Metropolis probability
+    /// `exp(-|delta| / temperature)`. The temperature cools geometrically
+    /// over `schedule.iterations` steps. Returns the best state seen and its
+    /// value.
+    pub fn anneal(e: &E, root: E::State, schedule: Schedule) -> (E::State, E::Value) {
+        let mut rng = Rng::seeded();
+        let mode = e.mode(&root);
+
+        let mut current = root.clone();
+        let mut current_value: f64 = e.evaluate(&current).into();
+
+        let mut best = root;
+        let mut best_value = current_value;
+
+        let mut temperature = schedule.initial_temperature;
+        for _ in 0..schedule.iterations {
+            let neighbors: Vec<_> = current.changes().collect();
+            let Some(change) = rng.weighted_choice(neighbors) else {
+                break;
+            };
+            let neighbor = current.apply(change);
+            let neighbor_value: f64 = e.evaluate(&neighbor).into();
+
+            let delta = neighbor_value - current_value;
+            let improves = match mode {
+                Mode::Maximize => delta > 0.0,
+                Mode::Minimize => delta < 0.0,
+            };
+            let accept =
+                improves || temperature > 0.0 && rng.next_f64() < (-delta.abs() / temperature).exp();
+
+            if accept {
+                current = neighbor;
+                current_value = neighbor_value;
+
+                let better_than_best = match mode {
+                    Mode::Maximize => current_value > best_value,
+                    Mode::Minimize => current_value < best_value,
+                };
+                if better_than_best {
+                    best = current.clone();
+                    best_value = current_value;
+                }
+            }
+
+            temperature *= schedule.cooling_factor;
+        }
+
+        (best.clone(), e.evaluate(&best))
+    }
+}
@@ -0,0 +1,128 @@
+//! Non-adversarial A*/uniform-cost search over [`State`], for finding the
+//! cheapest sequence of changes to a goal rather than playing an adversary.
+
+use crate::{Evaluator, Solver, State};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+/// Wraps an `f64` with a total order (NaN is treated as equal to everything)
+/// so it can be used as a [`BinaryHeap`] priority.
+#[derive(Clone, Copy, PartialEq)]
+struct FloatOrd(f64);
+
+impl Eq for FloatOrd {}
+
+impl PartialOrd for FloatOrd {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FloatOrd {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.partial_cmp(&other.0).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// A min-heap entry ordered by `f = g + h`, tie-broken by `g`.
+struct Entry<S> {
+    f: FloatOrd,
+    g: FloatOrd,
+    state: S,
+}
+
+impl<S> PartialEq for Entry<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.f == other.f && self.g == other.g
+    }
+}
+
+impl<S> Eq for Entry<S> {}
+
+impl<S> PartialOrd for Entry<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S> Ord for Entry<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so `BinaryHeap` (a max-heap) pops the smallest `f` first.
+        other.f.cmp(&self.f).then_with(|| other.g.cmp(&self.g))
+    }
+}
+
+/// Maps a state to the `(predecessor, change)` that reached it on the
+/// cheapest known path, for reconstructing the path once a goal pops.
+type CameFrom<E: Evaluator> = HashMap<<E as Evaluator>::State, PredecessorOf<E>>;
+type PredecessorOf<E: Evaluator> = (
+    <E as Evaluator>::State,
+    <<E as Evaluator>::State as State>::Change,
+);
+
+impl<E: Evaluator> Solver<E>
+where
+    E::Value: Into<f64>,
+{
+    /// Find the cheapest sequence of changes from `root` to a goal state (as
+    /// defined by [`Evaluator::is_goal`]), treating the `f64` weights from
+    /// [`State::changes`] as step costs and [`Evaluator::evaluate`] as an
+    /// admissible heuristic. Returns `None` if no goal is reachable.
+    pub fn search(e: &E, root: E::State) -> Option<Vec<<E::State as State>::Change>> {
+        let mut open = BinaryHeap::new();
+        let mut best_g: HashMap<E::State, f64> = HashMap::new();
+        let mut came_from: CameFrom<E> = HashMap::new();
+
+        best_g.insert(root.clone(), 0.0);
+        open.push(Entry {
+            f: FloatOrd(e.evaluate(&root).into()),
+            g: FloatOrd(0.0),
+            state: root,
+        });
+
+        while let Some(Entry { g, state, .. }) = open.pop() {
+            if e.is_goal(&state) {
+                return Some(reconstruct_path(&came_from, state));
+            }
+
+            // Skip stale entries: a cheaper path to this state was already found.
+            if g.0 > *best_g.get(&state).unwrap_or(&f64::INFINITY) {
+                continue;
+            }
+
+            for (cost, change) in state.changes() {
+                let neighbor = state.apply(change.clone());
+                let tentative_g = g.0 + cost;
+                if tentative_g < *best_g.get(&neighbor).unwrap_or(&f64::INFINITY) {
+                    best_g.insert(neighbor.clone(), tentative_g);
+                    came_from.insert(neighbor.clone(), (state.clone(), change));
+                    let h: f64 = e.evaluate(&neighbor).into();
+                    open.push(Entry {
+                        f: FloatOrd(tentative_g + h),
+                        g: FloatOrd(tentative_g),
+                        state: neighbor,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+}
+
+fn reconstruct_path<S, C>(
+    came_from: &HashMap<S, (S, C)>,
+    mut state: S,
+) -> Vec<C>
+where
+    S: Clone + Eq + std::hash::Hash,
+    C: Clone,
+{
+    let mut path = Vec::new();
+    while let Some((previous, change)) = came_from.get(&state) {
+        path.push(change.clone());
+        state = previous.clone();
+    }
+    path.reverse();
+    path
+}
@@ -1,11 +1,21 @@
 #![feature(lazy_type_alias)]
 
 use std::{
-    collections::{BinaryHeap, HashMap},
+    collections::HashMap,
     hash::Hash,
     ops::{Add, Div, Mul},
 };
 
+mod anneal;
+mod astar;
+mod mcts;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod rng;
+pub mod scorer;
+
+pub use anneal::Schedule;
+
 pub enum Mode {
     Minimize,
     Maximize,
@@ -29,233 +39,452 @@ pub trait Evaluator: Sized {
     /// Evaluate a state not considering future states
     fn evaluate(&self, state: &Self::State) -> Self::Value;
     fn mode(&self, state: &Self::State) -> Mode;
+    /// Lower bound used to seed the alpha window in alpha-beta pruning.
+    fn min_value(&self) -> Self::Value;
+    /// Upper bound used to seed the beta window in alpha-beta pruning.
+    fn max_value(&self) -> Self::Value;
+    /// Whether `state` is a goal state, used to terminate [`Solver::search`].
+    #[allow(unused)]
+    fn is_goal(&self, state: &Self::State) -> bool {
+        false
+    }
     #[allow(unused)]
     fn contemplate(&self, state: &Self::State, depth: usize) -> bool {
         true
     }
 }
 
-pub type Cache<E: Evaluator> = HashMap<<E as Evaluator>::State, Possibility<E>>;
+/// A handle into a [`Solver`]'s node arena. Cheap to copy and share, unlike
+/// an owned subtree.
+#[derive(Debug)]
+pub struct NodeId(usize);
+
+// Manual impls: `NodeId` is a plain index, copied and compared by that index.
+impl Clone for NodeId {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl Copy for NodeId {}
+impl PartialEq for NodeId {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+impl Eq for NodeId {}
+
+/// A node's children: one `(change, weight, child)` triple per move.
+type Children<E: Evaluator> = Vec<(<<E as Evaluator>::State as State>::Change, f64, NodeId)>;
+
+/// One node of the expanded tree, addressed by [`NodeId`] rather than owned
+/// by its parent. A state reached by more than one path (a transposition)
+/// lives here exactly once and is referenced by every parent that reaches it.
+struct Node<E: Evaluator> {
+    state: E::State,
+    /// Immediate, non-lookahead evaluation of `state`.
+    value: E::Value,
+    /// `None` until expanded; `Some(&[])` is impossible, an empty-children
+    /// state is simply never expanded past its immediate value.
+    children: Option<Children<E>>,
+}
+
+/// The transposition table: maps a state to the single arena slot holding it.
+pub type Cache<E: Evaluator> = HashMap<<E as Evaluator>::State, NodeId>;
 
 pub struct Solver<E: Evaluator> {
     evaluator: E,
-    tree: Possibility<E>,
+    arena: Vec<Node<E>>,
     cache: Cache<E>,
-}
-
-pub enum Possibility<E: Evaluator> {
-    Leaf {
-        state: E::State,
-        value: E::Value,
-    },
-    Branch {
-        state: E::State,
-        children: Vec<(<E::State as State>::Change, f64, Possibility<E>)>,
-    },
+    root: NodeId,
+    mcts: Option<mcts::MctsTree<E>>,
 }
 
 impl<E: Evaluator> Solver<E> {
     pub fn new(e: E, root: E::State) -> Self {
-        let mut cache = HashMap::new();
-        let tree = Possibility::new(root, &e, &mut cache);
-        Solver {
+        let mut solver = Solver {
             evaluator: e,
-            tree,
-            cache,
-        }
+            arena: Vec::new(),
+            cache: HashMap::new(),
+            root: NodeId(0),
+            mcts: None,
+        };
+        solver.root = solver.get_or_create(root);
+        solver
     }
 
-    pub fn choose(&mut self) -> Option<(E::Value, <E::State as State>::Change)> {
-        self.tree.choose(&self.evaluator, &mut self.cache)
+    /// Look up `state` in the transposition table, or evaluate and insert it.
+    fn get_or_create(&mut self, state: E::State) -> NodeId {
+        if let Some(&id) = self.cache.get(&state) {
+            return id;
+        }
+        let value = self.evaluator.evaluate(&state);
+        let id = NodeId(self.arena.len());
+        self.cache.insert(state.clone(), id);
+        self.arena.push(Node {
+            state,
+            value,
+            children: None,
+        });
+        id
     }
 
-    pub fn select(&mut self, change: <E::State as State>::Change) -> &mut Self {
-        self.tree.select(change, &self.evaluator, &mut self.cache);
-        self
+    /// Expand `id` one ply, or recurse into its already-expanded children to
+    /// expand the frontier one ply further. Transposed children are only
+    /// ever evaluated/stored once, however many parents reach them.
+    fn expand(&mut self, id: NodeId, depth: usize) {
+        let state = self.arena[id.0].state.clone();
+        if !self.evaluator.contemplate(&state, depth) {
+            return;
+        }
+        if self.arena[id.0].children.is_none() {
+            let changes: Vec<_> = state.changes().collect();
+            if changes.is_empty() {
+                return;
+            }
+            let mut children = Vec::with_capacity(changes.len());
+            for (weight, change) in changes {
+                let child_state = state.apply(change.clone());
+                let child_id = self.get_or_create(child_state);
+                children.push((change, weight, child_id));
+            }
+            self.arena[id.0].children = Some(children);
+        } else {
+            let child_ids: Vec<NodeId> = self.arena[id.0]
+                .children
+                .as_ref()
+                .unwrap()
+                .iter()
+                .map(|(_, _, child_id)| *child_id)
+                .collect();
+            for child_id in child_ids {
+                self.expand(child_id, depth + 1);
+            }
+        }
     }
 
-    pub fn state(&self) -> &E::State {
-        self.tree.state()
+    /// Rescales an `(alpha, beta)` window from a parent's value scale down
+    /// to the scale of a `weight`-ed child, so it can be passed into that
+    /// child's own `evaluate_ab` and still mean the same bound once the
+    /// child's result is multiplied back by `weight`. Flips the bounds when
+    /// `weight` is negative, since multiplying reverses order. Callers must
+    /// not pass a zero `weight`: see [`Solver::evaluate_child`].
+    fn rescale_window(&self, alpha: E::Value, beta: E::Value, weight: f64) -> (E::Value, E::Value) {
+        if weight > 0.0 {
+            (alpha / weight, beta / weight)
+        } else {
+            (beta / weight, alpha / weight)
+        }
     }
-}
 
-impl<E: Evaluator> Possibility<E> {
-    pub fn new(root: E::State, e: &E, cache: &mut Cache<E>) -> Self {
-        if let Some(possibility) = cache.get(&root) {
-            return possibility.clone();
+    /// Evaluates one weighted child edge. A zero-weight edge always
+    /// contributes zero to the parent no matter what its subtree evaluates
+    /// to, so its subtree is never explored -- the cached immediate value
+    /// is multiplied by the weight directly instead.
+    fn evaluate_child(
+        &mut self,
+        child_id: NodeId,
+        depth: usize,
+        alpha: E::Value,
+        beta: E::Value,
+        weight: f64,
+    ) -> E::Value {
+        if weight == 0.0 {
+            return self.arena[child_id.0].value.clone() * weight;
         }
-        let value = e.evaluate(&root);
-        Self::Leaf { state: root, value }
+        let (child_alpha, child_beta) = self.rescale_window(alpha, beta, weight);
+        self.evaluate_ab(child_id, depth, child_alpha, child_beta) * weight
     }
 
-    pub fn select(&mut self, change: <E::State as State>::Change, e: &E, cache: &mut Cache<E>) {
-        self.expand(e, cache, 0);
-        match self {
-            Possibility::Leaf { .. } => {
-                panic!("cannot select on leaf")
+    /// Alpha-beta pruned minimax evaluation of `id`, cutting off branches
+    /// that cannot influence the final decision once the `(alpha, beta)`
+    /// window proves a subtree is irrelevant.
+    fn evaluate_ab(
+        &mut self,
+        id: NodeId,
+        depth: usize,
+        mut alpha: E::Value,
+        mut beta: E::Value,
+    ) -> E::Value {
+        if self.evaluator.contemplate(&self.arena[id.0].state, depth) {
+            self.expand(id, depth);
+        }
+
+        let Some(mut children) = self.arena[id.0].children.clone() else {
+            return self.arena[id.0].value.clone();
+        };
+
+        let mode = self.evaluator.mode(&self.arena[id.0].state);
+
+        // Order children by their cached immediate evaluation so the most
+        // promising moves are explored first, maximizing cutoffs.
+        children.sort_by(|(_, _, a), (_, _, b)| {
+            let a = &self.arena[a.0].value;
+            let b = &self.arena[b.0].value;
+            let ordering = a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal);
+            match mode {
+                Mode::Maximize => ordering.reverse(),
+                Mode::Minimize => ordering,
             }
-            Possibility::Branch { children, .. } => {
-                for (c, _, child) in children {
-                    if *c == change {
-                        *self = child.clone();
-                        return;
+        });
+
+        let mut best: Option<E::Value> = None;
+        for (_, weight, child_id) in children {
+            let value = self.evaluate_child(child_id, depth + 1, alpha.clone(), beta.clone(), weight);
+            best = Some(match (&best, &mode) {
+                (None, _) => value,
+                (Some(acc), Mode::Maximize) if value > *acc => value,
+                (Some(acc), Mode::Minimize) if value < *acc => value,
+                (Some(acc), _) => acc.clone(),
+            });
+            let best_so_far = best.clone().unwrap();
+            match mode {
+                Mode::Maximize => {
+                    if best_so_far > alpha {
+                        alpha = best_so_far;
+                    }
+                    if alpha >= beta {
+                        break;
+                    }
+                }
+                Mode::Minimize => {
+                    if best_so_far < beta {
+                        beta = best_so_far;
+                    }
+                    if beta <= alpha {
+                        break;
                     }
                 }
             }
         }
+        best.expect("There is at least one child.")
     }
 
-    fn create(state: E::State, e: &E, cache: &mut Cache<E>, depth: usize) -> Self {
-        if let Some(possibility) = cache.get(&state) {
-            return possibility.clone();
-        }
-        let value = e.evaluate(&state);
-        if !e.contemplate(&state, depth) {
-            let value = e.evaluate(&state);
-            return Self::Leaf { state, value };
+    pub fn choose(&mut self) -> Option<(E::Value, <E::State as State>::Change)> {
+        self.expand(self.root, 0);
+        let children = self.arena[self.root.0].children.clone()?;
+        // `choose` always keeps the smallest value seen across root moves
+        // (see the sort/pop below), so `beta` is the tightest upper bound
+        // on "a value that could still beat the current best" and tightens
+        // as siblings are visited, pruning across root moves rather than
+        // only within each child's own subtree.
+        let alpha = self.evaluator.min_value();
+        let mut beta = self.evaluator.max_value();
+        let mut heap = Vec::new();
+        for (change, weight, child_id) in children {
+            let value = self.evaluate_child(child_id, 1, alpha.clone(), beta.clone(), weight);
+            if value < beta {
+                beta = value.clone();
+            }
+            heap.push((value, change));
         }
-        let children: Vec<(<E::State as State>::Change, f64, Possibility<E>)> = state
-            .changes()
-            .map(|(weight, change)| {
-                let child = Self::create(state.apply(change.clone()), e, cache, depth + 1);
-                (change, weight, child)
+        heap.sort_by(|(a, _), (b, _)| {
+            b.partial_cmp(a).unwrap_or_else(|| {
+                dbg!(a, b);
+                unreachable!()
             })
-            .collect();
-        let re = if children.is_empty() {
-            Self::Leaf {
-                state: state.clone(),
-                value,
-            }
-        } else {
-            Self::Branch {
-                state: state.clone(),
-                children,
-            }
-        };
-        cache.insert(state, re.clone());
-        re
+        });
+        heap.pop()
     }
 
-    pub fn expand(&mut self, e: &E, cache: &mut Cache<E>, depth: usize) {
-        if !e.contemplate(self.state(), depth) {
-            return;
+    pub fn select(&mut self, change: <E::State as State>::Change) -> &mut Self {
+        self.expand(self.root, 0);
+        match &self.arena[self.root.0].children {
+            None => panic!("cannot select on leaf"),
+            Some(children) => {
+                if let Some((_, _, child_id)) = children.iter().find(|(c, _, _)| *c == change) {
+                    self.root = *child_id;
+                }
+            }
         }
-        match self {
-            Possibility::Leaf { state, .. } => {
-                let children: Vec<(<E::State as State>::Change, f64, Possibility<E>)> = state
-                    .changes()
-                    .map(|(weight, change)| {
-                        let child = Self::create(state.apply(change.clone()), e, cache, depth + 1);
-                        (change, weight, child)
-                    })
-                    .collect();
-                if children.is_empty() {
-                    return;
+        self.prune_unreachable();
+        self
+    }
+
+    /// Drops arena entries (and their transposition-table rows) that are no
+    /// longer reachable from the current root, so re-rooting via `select`
+    /// doesn't keep every historically-visited node resident for as long as
+    /// the `Solver` lives. Runs after every `select`, since that's the only
+    /// place the root -- and so reachability -- changes.
+    fn prune_unreachable(&mut self) {
+        let mut reachable = vec![false; self.arena.len()];
+        let mut stack = vec![self.root];
+        while let Some(id) = stack.pop() {
+            if reachable[id.0] {
+                continue;
+            }
+            reachable[id.0] = true;
+            if let Some(children) = &self.arena[id.0].children {
+                for (_, _, child_id) in children {
+                    stack.push(*child_id);
                 }
-                // possible optimization: don't clone state
-                *self = Possibility::Branch {
-                    state: state.clone(),
-                    children,
-                };
             }
-            Possibility::Branch { children, .. } => {
-                for (_, _, child) in children {
-                    child.expand(e, cache, depth + 1);
+        }
+
+        let old_arena = std::mem::take(&mut self.arena);
+        let mut remap = vec![None; old_arena.len()];
+        let mut new_arena = Vec::with_capacity(old_arena.len());
+        for (old_id, node) in old_arena.into_iter().enumerate() {
+            if reachable[old_id] {
+                remap[old_id] = Some(NodeId(new_arena.len()));
+                new_arena.push(node);
+            }
+        }
+        for node in &mut new_arena {
+            if let Some(children) = &mut node.children {
+                for (_, _, child_id) in children.iter_mut() {
+                    *child_id = remap[child_id.0].expect("child of a reachable node is reachable");
                 }
             }
         }
+
+        self.cache.retain(|_, id| remap[id.0].is_some());
+        for id in self.cache.values_mut() {
+            *id = remap[id.0].expect("cache entries are only ever inserted for arena nodes");
+        }
+        self.root = remap[self.root.0].expect("root is always reachable from itself");
+        self.arena = new_arena;
+    }
+
+    pub fn state(&self) -> &E::State {
+        &self.arena[self.root.0].state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A tiny fixed game tree with non-uniform and zero edge weights, used
+    /// to check `evaluate_ab`/`choose` against a plain, unpruned minimax
+    /// oracle. Layout:
+    /// `Root --1.0--> M0` (leaf, 2.0)
+    /// `Root --0.0001--> M1 --1.0--> M1a` (leaf, -5.0) `/ --1.0--> M1b` (leaf, 5.0)
+    /// `Root --0.0--> M2 --1.0--> M2a` (leaf, 999.0)
+    #[derive(Clone, Hash, PartialEq, Eq, Debug)]
+    enum TreeState {
+        Root,
+        M0,
+        M1,
+        M1a,
+        M1b,
+        M2,
+        M2a,
     }
 
-    pub fn evaluate(&mut self, e: &E, cache: &mut Cache<E>, depth: usize) -> E::Value {
-        // If the evaluator doesn’t want us to look ahead (or we are in a terminal state),
-        // just return the immediate evaluation.
-        if e.contemplate(self.state(), depth) {
-            self.expand(e, cache, depth);
+    struct TreeEval;
+
+    impl Evaluator for TreeEval {
+        type State = TreeState;
+        type Value = f64;
+
+        fn evaluate(&self, state: &TreeState) -> f64 {
+            match state {
+                TreeState::M0 => 2.0,
+                TreeState::M1a => -5.0,
+                TreeState::M1b => 5.0,
+                // M2's own immediate value is deliberately extreme: it must
+                // never influence the result, since its edge weight is 0.
+                TreeState::M2 | TreeState::M2a => 999.0,
+                TreeState::Root | TreeState::M1 => 0.0,
+            }
         }
 
-        match self {
-            Possibility::Leaf { value, .. } => value.clone(),
-            Possibility::Branch { state, children } => {
-                // Determine whether we are maximizing or minimizing at this state.
-                let mode = e.mode(state);
-
-                // Use a fold (or alternatively, iterate and track the best value)
-                // to compute the best evaluation among the children.
-                let child = children
-                    .into_iter()
-                    .map(|(_, weight, child)| child.evaluate(e, cache, depth + 1) * *weight)
-                    .fold(None, |acc: Option<E::Value>, cur| match acc {
-                        None => Some(cur),
-                        Some(acc_val) => match mode {
-                            Mode::Maximize => {
-                                if cur > acc_val {
-                                    Some(cur)
-                                } else {
-                                    Some(acc_val)
-                                }
-                            }
-                            Mode::Minimize => {
-                                if cur < acc_val {
-                                    Some(cur)
-                                } else {
-                                    Some(acc_val)
-                                }
-                            }
-                        },
-                    })
-                    .expect("There is at least one child.");
-
-                // In this design we add the immediate evaluated value for the state
-                // (for possible heuristic benefits) and then propagate the best child’s value.
-                child
+        fn mode(&self, state: &TreeState) -> Mode {
+            match state {
+                TreeState::M1 => Mode::Minimize,
+                _ => Mode::Maximize,
             }
         }
+
+        fn min_value(&self) -> f64 {
+            f64::NEG_INFINITY
+        }
+
+        fn max_value(&self) -> f64 {
+            f64::INFINITY
+        }
     }
 
-    pub fn choose(
-        &mut self,
-        e: &E,
-        cache: &mut Cache<E>,
-    ) -> Option<(E::Value, <E::State as State>::Change)> {
-        self.expand(e, cache, 0);
-        match self {
-            Possibility::Leaf { .. } => None,
-            Possibility::Branch { children, .. } => {
-                let mut heap = Vec::new();
-                for (change, weight, child) in children {
-                    let value = child.evaluate(e, cache, 1) * *weight;
-                    heap.push((value, change));
-                }
-                heap.sort_by(|(a, _), (b, _)| {
-                    b.partial_cmp(a).unwrap_or_else(|| {
-                        dbg!(a, b);
-                        unreachable!()
-                    })
-                });
-                heap.pop().map(|(value, change)| (value, change.clone()))
+    impl State for TreeState {
+        type Change = u8;
+
+        fn apply(&self, action: u8) -> Self {
+            match (self, action) {
+                (TreeState::Root, 0) => TreeState::M0,
+                (TreeState::Root, 1) => TreeState::M1,
+                (TreeState::Root, 2) => TreeState::M2,
+                (TreeState::M1, 0) => TreeState::M1a,
+                (TreeState::M1, 1) => TreeState::M1b,
+                (TreeState::M2, 0) => TreeState::M2a,
+                _ => unreachable!("no such move from this state"),
             }
         }
-    }
 
-    pub fn state(&self) -> &E::State {
-        match self {
-            Possibility::Leaf { state, .. } => state,
-            Possibility::Branch { state, .. } => state,
+        fn changes(&self) -> impl Iterator<Item = (f64, u8)> {
+            match self {
+                TreeState::Root => vec![(1.0, 0u8), (0.0001, 1u8), (0.0, 2u8)],
+                TreeState::M1 => vec![(1.0, 0u8), (1.0, 1u8)],
+                TreeState::M2 => vec![(1.0, 0u8)],
+                _ => vec![],
+            }
+            .into_iter()
         }
     }
-}
 
-impl<E: Evaluator> Clone for Possibility<E> {
-    fn clone(&self) -> Self {
-        match self {
-            Possibility::Leaf { state, value } => Possibility::Leaf {
-                state: state.clone(),
-                value: value.clone(),
-            },
-            Possibility::Branch { state, children } => Possibility::Branch {
-                state: state.clone(),
-                children: children.clone(),
-            },
+    /// Plain, unpruned weighted minimax over a [`TreeState`], independent of
+    /// [`Solver`], used as an oracle for the tests below.
+    fn minimax(eval: &TreeEval, state: &TreeState) -> f64 {
+        let moves: Vec<_> = state.changes().collect();
+        if moves.is_empty() {
+            return eval.evaluate(state);
         }
+        let mode = eval.mode(state);
+        moves
+            .into_iter()
+            .map(|(weight, change)| minimax(eval, &state.apply(change)) * weight)
+            .fold(None, |acc: Option<f64>, value| {
+                Some(match acc {
+                    None => value,
+                    Some(acc) => match mode {
+                        Mode::Maximize => acc.max(value),
+                        Mode::Minimize => acc.min(value),
+                    },
+                })
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn choose_matches_weighted_minimax_oracle() {
+        // `choose` always returns the smallest value across root moves
+        // regardless of `Evaluator::mode` (a deliberate, documented
+        // convention -- see the comment inside `choose`), so the oracle here
+        // mirrors that rather than doing a mode-aware root fold.
+        let expected_value = TreeState::Root
+            .changes()
+            .map(|(weight, change)| minimax(&TreeEval, &TreeState::Root.apply(change)) * weight)
+            .fold(f64::INFINITY, f64::min);
+
+        let mut solver = Solver::new(TreeEval, TreeState::Root);
+        let (value, _) = solver.choose().expect("root has moves");
+        assert_eq!(value, expected_value);
+    }
+
+    #[test]
+    fn select_prunes_unreachable_nodes_and_keeps_solver_correct() {
+        let mut solver = Solver::new(TreeEval, TreeState::Root);
+        solver.choose();
+
+        solver.select(1); // Root -> M1
+        assert_eq!(*solver.state(), TreeState::M1);
+        // Only M1 and its two children remain reachable from the new root;
+        // Root/M0/M2 (and anything `select`'s own re-expansion pass added
+        // under them) must have been dropped from both the arena and the
+        // transposition cache.
+        assert_eq!(solver.arena.len(), 3);
+        assert_eq!(solver.cache.len(), 3);
+
+        let (value, _) = solver.choose().expect("M1 has moves");
+        assert_eq!(value, minimax(&TreeEval, &TreeState::M1));
     }
 }
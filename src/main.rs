@@ -80,6 +80,14 @@ impl Evaluator for Eval {
         }
     }
 
+    fn min_value(&self) -> Self::Value {
+        f64::NEG_INFINITY
+    }
+
+    fn max_value(&self) -> Self::Value {
+        f64::INFINITY
+    }
+
     fn contemplate(&self, state: &Self::State, depth: usize) -> bool {
         true
     }
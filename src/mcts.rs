@@ -0,0 +1,171 @@
+//! Monte Carlo Tree Search: builds its own tree by repeated
+//! selection/expansion/simulation/backpropagation rounds rather than
+//! expanding every move up front, so it scales to branching factors the
+//! exhaustive [`crate::Solver::choose`] can't afford to enumerate.
+
+use crate::rng::Rng;
+use crate::{Evaluator, Mode, Solver, State};
+
+/// Exploration constant used in the UCB1 formula.
+const EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+/// Depth cap for random playouts, in case a state never terminates.
+const ROLLOUT_DEPTH_CAP: usize = 128;
+
+struct MctsNode<E: Evaluator> {
+    state: E::State,
+    visits: u32,
+    value_sum: f64,
+    children: Vec<(<E::State as State>::Change, f64, usize)>,
+    untried: Vec<(f64, <E::State as State>::Change)>,
+}
+
+impl<E: Evaluator> MctsNode<E> {
+    fn new(state: E::State) -> Self {
+        let untried = state.changes().collect();
+        MctsNode {
+            state,
+            visits: 0,
+            value_sum: 0.0,
+            children: Vec::new(),
+            untried,
+        }
+    }
+}
+
+pub struct MctsTree<E: Evaluator> {
+    nodes: Vec<MctsNode<E>>,
+    root: usize,
+}
+
+impl<E: Evaluator> MctsTree<E>
+where
+    E::Value: Into<f64>,
+{
+    fn new(root: E::State) -> Self {
+        MctsTree {
+            nodes: vec![MctsNode::new(root)],
+            root: 0,
+        }
+    }
+
+    /// One selection/expansion/simulation/backpropagation round.
+    fn iterate(&mut self, e: &E, rng: &mut Rng) {
+        let mut path = vec![self.root];
+
+        // 1. Selection: descend picking the UCB1-best child until we reach a
+        // node with unexpanded moves (or no moves at all).
+        let mut current = self.root;
+        while self.nodes[current].untried.is_empty() && !self.nodes[current].children.is_empty() {
+            current = self.select_child(e, current);
+            path.push(current);
+        }
+
+        // 2. Expansion: add one child for an untried move, if any exist.
+        if let Some((weight, change)) = self.nodes[current].untried.pop() {
+            let child_state = self.nodes[current].state.apply(change.clone());
+            let child_index = self.nodes.len();
+            self.nodes.push(MctsNode::new(child_state));
+            self.nodes[current]
+                .children
+                .push((change, weight, child_index));
+            path.push(child_index);
+            current = child_index;
+        }
+
+        // 3. Simulation: random playout from the newly reached state.
+        let value: f64 = self.rollout(e, &self.nodes[current].state, rng).into();
+
+        // 4. Backpropagation: record the rollout outcome along the path.
+        for &node in &path {
+            self.nodes[node].visits += 1;
+            self.nodes[node].value_sum += value;
+        }
+    }
+
+    fn select_child(&self, e: &E, parent: usize) -> usize {
+        let parent_visits = self.nodes[parent].visits;
+        let mode = e.mode(&self.nodes[parent].state);
+        self.nodes[parent]
+            .children
+            .iter()
+            .max_by(|(_, _, a), (_, _, b)| {
+                let a = self.ucb1(parent_visits, *a, &mode);
+                let b = self.ucb1(parent_visits, *b, &mode);
+                a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .map(|(_, _, child)| *child)
+            .expect("select_child called on a node with no children")
+    }
+
+    /// UCB1 score for `child`, with the exploitation term negated according
+    /// to whose turn it is so that a higher score is always better.
+    fn ucb1(&self, parent_visits: u32, child: usize, mode: &Mode) -> f64 {
+        let child = &self.nodes[child];
+        if child.visits == 0 {
+            return f64::INFINITY;
+        }
+        let average = child.value_sum / child.visits as f64;
+        let exploitation = match mode {
+            Mode::Maximize => average,
+            Mode::Minimize => -average,
+        };
+        exploitation + EXPLORATION * ((parent_visits as f64).ln() / child.visits as f64).sqrt()
+    }
+
+    fn rollout(&self, e: &E, from: &E::State, rng: &mut Rng) -> E::Value {
+        let mut state = from.clone();
+        for _ in 0..ROLLOUT_DEPTH_CAP {
+            let changes: Vec<_> = state.changes().collect();
+            if changes.is_empty() {
+                break;
+            }
+            let Some(change) = rng.weighted_choice(changes) else {
+                break;
+            };
+            state = state.apply(change);
+        }
+        e.evaluate(&state)
+    }
+
+    fn best_child(&self) -> Option<(f64, <E::State as State>::Change)> {
+        let root = &self.nodes[self.root];
+        root.children
+            .iter()
+            .max_by_key(|(_, _, child)| self.nodes[*child].visits)
+            .map(|(change, _, child)| {
+                let node = &self.nodes[*child];
+                let average = if node.visits > 0 {
+                    node.value_sum / node.visits as f64
+                } else {
+                    0.0
+                };
+                (average, change.clone())
+            })
+    }
+}
+
+impl<E: Evaluator> Solver<E>
+where
+    E::Value: Into<f64>,
+{
+    /// Build a solver and run `iterations` rounds of Monte Carlo Tree Search
+    /// from `root`, as a cheaper alternative to the exhaustive expansion
+    /// that backs [`Solver::choose`] when the branching factor is too large.
+    pub fn mcts(e: E, root: E::State, iterations: usize) -> Self {
+        let mut solver = Solver::new(e, root.clone());
+        let mut tree = MctsTree::new(root);
+        let mut rng = Rng::seeded();
+        for _ in 0..iterations {
+            tree.iterate(&solver.evaluator, &mut rng);
+        }
+        solver.mcts = Some(tree);
+        solver
+    }
+
+    /// Return the root child with the most visits, along with its average
+    /// rollout value, as found by the search run in [`Solver::mcts`].
+    pub fn choose_mcts(&self) -> Option<(f64, <E::State as State>::Change)> {
+        self.mcts.as_ref()?.best_child()
+    }
+}
@@ -0,0 +1,133 @@
+//! A `rayon`-backed parallel minimax, for games whose sibling subtrees are
+//! independent enough that evaluating them concurrently pays for itself.
+//! Only built with the `rayon` feature enabled; the sequential path in
+//! [`crate::Solver`] remains the default.
+
+use crate::{Evaluator, Mode, Solver, State};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Transposition cache of each visited state's minimax value, shared across
+/// worker threads behind a single lock. Contention stays low because each
+/// entry is a single value, not a whole subtree.
+type SharedCache<E: Evaluator> = Mutex<HashMap<<E as Evaluator>::State, <E as Evaluator>::Value>>;
+
+impl<E: Evaluator> Solver<E>
+where
+    E: Sync,
+    E::State: Send + Sync,
+    E::Value: Send,
+{
+    /// Parallel minimax evaluation of `state`. Forks into the rayon thread
+    /// pool for nodes shallower than `fork_depth` and walks sequentially
+    /// below it, to avoid paying task overhead on tiny subtrees.
+    pub fn evaluate_parallel(&self, state: &E::State, fork_depth: usize) -> E::Value {
+        let cache = SharedCache::<E>::default();
+        evaluate_parallel_at(&self.evaluator, state, 0, fork_depth, &cache)
+    }
+
+    /// Parallel counterpart to [`Solver::choose`]: evaluates each of the
+    /// root's moves with [`Solver::evaluate_parallel`] and, like `choose`,
+    /// always returns the *smallest* value across root moves regardless of
+    /// [`Evaluator::mode`] -- this is `choose`'s long-standing convention,
+    /// not an oversight, and the two must agree on the chosen move.
+    pub fn choose_parallel(&self, fork_depth: usize) -> Option<(E::Value, <E::State as State>::Change)> {
+        let root = self.state();
+        let cache = SharedCache::<E>::default();
+
+        root.changes()
+            .map(|(weight, change)| {
+                let child = root.apply(change.clone());
+                let value = evaluate_parallel_at(&self.evaluator, &child, 1, fork_depth, &cache) * weight;
+                (value, change)
+            })
+            .fold(None, |best: Option<(E::Value, _)>, (value, change)| {
+                match &best {
+                    Some((best_value, _)) if value >= *best_value => best,
+                    _ => Some((value, change)),
+                }
+            })
+    }
+}
+
+/// Evaluates `state` via minimax, forking sibling subtrees into the rayon
+/// thread pool while shallower than `fork_depth` and falling back to a
+/// sequential walk below it. Takes `evaluator` directly (rather than a
+/// `&Solver<E>`) so the recursion, and the closures rayon sends across
+/// threads, never need `Solver`'s arena (`Vec<Node<E>>`) to be `Sync` --
+/// only `E` and the small per-call state need to be.
+fn evaluate_parallel_at<E>(
+    evaluator: &E,
+    state: &E::State,
+    depth: usize,
+    fork_depth: usize,
+    cache: &SharedCache<E>,
+) -> E::Value
+where
+    E: Evaluator + Sync,
+    E::State: Send + Sync,
+    E::Value: Send,
+{
+    if let Some(value) = cache.lock().unwrap().get(state) {
+        return value.clone();
+    }
+
+    let children: Vec<(f64, E::State)> = if evaluator.contemplate(state, depth) {
+        state
+            .changes()
+            .map(|(weight, change)| (weight, state.apply(change)))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    if children.is_empty() {
+        let value = evaluator.evaluate(state);
+        cache.lock().unwrap().insert(state.clone(), value.clone());
+        return value;
+    }
+
+    let evaluate_child = |weight: f64, child: &E::State| {
+        evaluate_parallel_at(evaluator, child, depth + 1, fork_depth, cache) * weight
+    };
+
+    let values: Vec<E::Value> = if depth < fork_depth {
+        children
+            .par_iter()
+            .map(|(weight, child)| evaluate_child(*weight, child))
+            .collect()
+    } else {
+        children
+            .iter()
+            .map(|(weight, child)| evaluate_child(*weight, child))
+            .collect()
+    };
+
+    let mode = evaluator.mode(state);
+    let value = values
+        .into_iter()
+        .fold(None, |acc: Option<E::Value>, cur| match acc {
+            None => Some(cur),
+            Some(acc_val) => match mode {
+                Mode::Maximize => {
+                    if cur > acc_val {
+                        Some(cur)
+                    } else {
+                        Some(acc_val)
+                    }
+                }
+                Mode::Minimize => {
+                    if cur < acc_val {
+                        Some(cur)
+                    } else {
+                        Some(acc_val)
+                    }
+                }
+            },
+        })
+        .expect("There is at least one child.");
+
+    cache.lock().unwrap().insert(state.clone(), value.clone());
+    value
+}
@@ -0,0 +1,46 @@
+//! Tiny splitmix64 PRNG shared by the search modes that need randomness
+//! ([`crate::mcts`], [`crate::anneal`]), so the crate doesn't need an
+//! external dependency just to sample a playout or a neighbor.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn seeded() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0x9E3779B97F4A7C15);
+        Rng(nanos ^ 0x9E3779B97F4A7C15)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform float in `[0, 1)`.
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Picks a `(weight, item)` pair at random, weighted by `weight`.
+    pub(crate) fn weighted_choice<T>(&mut self, items: Vec<(f64, T)>) -> Option<T> {
+        let total: f64 = items.iter().map(|(w, _)| w).sum();
+        if total <= 0.0 {
+            return items.into_iter().next().map(|(_, item)| item);
+        }
+        let mut pick = self.next_f64() * total;
+        for (weight, item) in items {
+            if pick < weight {
+                return Some(item);
+            }
+            pick -= weight;
+        }
+        None
+    }
+}
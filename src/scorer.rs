@@ -0,0 +1,92 @@
+//! A utility-AI layer: independent [`Scorer`]s combined into a single
+//! [`CompositeEvaluator`], so a user can register considerations like
+//! "material", "mobility", or "king safety" as separate, weighted, tunable
+//! pieces instead of hand-rolling one evaluation function.
+
+use crate::{Evaluator, Mode, State};
+
+/// One independent consideration a [`CompositeEvaluator`] scores a state by.
+pub trait Scorer<S> {
+    fn score(&self, state: &S) -> f64;
+}
+
+/// How a [`CompositeEvaluator`] combines its scorers' normalized outputs.
+pub enum Aggregator {
+    /// Weighted average of the normalized scores.
+    WeightedSum,
+    /// The smallest weighted normalized score (each normalized score
+    /// multiplied by its weight).
+    /// Use this for "all considerations must be satisfied" semantics.
+    Min,
+    /// The product of the weighted normalized scores.
+    Product,
+}
+
+/// Combines several weighted [`Scorer`]s into one [`Evaluator`].
+pub struct CompositeEvaluator<S> {
+    scorers: Vec<(Box<dyn Scorer<S>>, f64)>,
+    aggregator: Aggregator,
+}
+
+impl<S> CompositeEvaluator<S> {
+    pub fn new(aggregator: Aggregator) -> Self {
+        CompositeEvaluator {
+            scorers: Vec::new(),
+            aggregator,
+        }
+    }
+
+    /// Register a scorer with its weight. Order doesn't matter.
+    pub fn with_scorer(mut self, scorer: impl Scorer<S> + 'static, weight: f64) -> Self {
+        self.scorers.push((Box::new(scorer), weight));
+        self
+    }
+
+    /// Squashes an unbounded scorer output into `(0, 1)` so scorers with
+    /// different natural ranges (a raw material count vs. a -1..1 safety
+    /// score) combine on comparable footing.
+    fn normalize(raw: f64) -> f64 {
+        1.0 / (1.0 + (-raw).exp())
+    }
+}
+
+impl<S: State> Evaluator for CompositeEvaluator<S> {
+    type State = S;
+    type Value = f64;
+
+    fn evaluate(&self, state: &Self::State) -> Self::Value {
+        if self.scorers.is_empty() {
+            return 0.0;
+        }
+        let normalized = self
+            .scorers
+            .iter()
+            .map(|(scorer, weight)| (Self::normalize(scorer.score(state)), *weight));
+
+        match self.aggregator {
+            Aggregator::WeightedSum => {
+                let total_weight: f64 = self.scorers.iter().map(|(_, w)| w).sum();
+                if total_weight == 0.0 {
+                    return 0.0;
+                }
+                normalized.map(|(score, weight)| score * weight).sum::<f64>() / total_weight
+            }
+            Aggregator::Min => normalized
+                .map(|(score, weight)| score * weight)
+                .fold(f64::INFINITY, f64::min),
+            Aggregator::Product => normalized.map(|(score, weight)| score * weight).product(),
+        }
+    }
+
+    fn mode(&self, _state: &Self::State) -> Mode {
+        Mode::Maximize
+    }
+
+    fn min_value(&self) -> Self::Value {
+        f64::NEG_INFINITY
+    }
+
+    fn max_value(&self) -> Self::Value {
+        f64::INFINITY
+    }
+}